@@ -1,21 +1,56 @@
 #[macro_use]
 mod error;
+mod onion;
 
 use arti::socks;
-use arti_client::{DormantMode, TorClient, TorClientConfig};
+use arti_client::isolation::IsolationToken;
+use arti_client::{DataStream, DormantMode, StreamPrefs, TorClient, TorClientConfig};
 use arti_client::config::CfgPath;
+use futures::StreamExt;
 use lazy_static::lazy_static;
+use std::collections::HashMap;
 use std::ffi::{c_char, c_void, CStr, CString};
-use std::{io, ptr};
+use std::path::Path;
+use std::sync::Mutex;
+use std::{io, ptr, slice};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::runtime::{Builder, Runtime};
 use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
+use tor_guardmgr::bridge::BridgeConfigBuilder;
+use tor_ptmgr::config::TransportConfigBuilder;
 use tor_rtcompat::tokio::TokioNativeTlsRuntime;
 use tor_rtcompat::BlockOn;
 
 lazy_static! {
     // Initialize a Tokio runtime once and reuse it throughout the application.
-    static ref RUNTIME: io::Result<Runtime> = Builder::new_multi_thread().enable_all().build();
+    pub(crate) static ref RUNTIME: io::Result<Runtime> = Builder::new_multi_thread().enable_all().build();
+
+    // Maps caller-supplied isolation tokens (plain u64s, convenient to pass
+    // over FFI) to the arti-side `IsolationToken`s they designate, so that
+    // repeated calls with the same numeric token keep sharing one isolation
+    // group instead of minting a fresh one each time.
+    static ref ISOLATION_TOKENS: Mutex<HashMap<u64, IsolationToken>> = Mutex::new(HashMap::new());
+}
+
+// Looks up (or creates) the `IsolationToken` for a caller-supplied token id.
+fn isolation_token_for(id: u64) -> IsolationToken {
+    *ISOLATION_TOKENS
+        .lock()
+        .unwrap()
+        .entry(id)
+        .or_insert_with(IsolationToken::new)
+}
+
+// Forgets the `IsolationToken` associated with a caller-supplied token id.
+// Call this once a logical session ends (e.g. a user logs out of one of the
+// accounts from the example on `arti_connect_isolated`), so `ISOLATION_TOKENS` doesn't
+// grow for the lifetime of the process. A later call to
+// `arti_connect_isolated` with the same id mints a fresh isolation group,
+// same as if the id had never been used.
+#[no_mangle]
+pub extern "C" fn arti_release_isolation_token(isolation_token: u64) {
+    ISOLATION_TOKENS.lock().unwrap().remove(&isolation_token);
 }
 
 #[repr(C)] // Ensure struct has a defined layout for FFI compatibility.
@@ -26,23 +61,169 @@ pub struct Tor {
     progress_receiver: *mut c_void,
 }
 
+// Describes one pluggable transport (e.g. obfs4, snowflake) to register with
+// the client's bridge configuration. `args` is a C array of `args_len`
+// NUL-terminated strings, passed on the PT binary's command line.
+#[repr(C)]
+pub struct PluggableTransportDescriptor {
+    name: *const c_char,
+    binary_path: *const c_char,
+    args: *const *const c_char,
+    args_len: usize,
+}
+
+// Bundles the spawned SOCKS proxy task together with the client clone that
+// kept it running. Dropping the bundle (`arti_proxy_stop`) drops only *this*
+// clone; it does not by itself tear down the client's pluggable-transport
+// subprocesses, since `Tor.client` — and any handle returned by
+// `arti_isolated_client` — still holds its own clone. Full teardown
+// (including killing spawned PT binaries like obfs4proxy) requires calling
+// `arti_client_free` on every outstanding client/isolated-client handle too.
+struct ProxyHandle {
+    join: JoinHandle<anyhow::Result<()>>,
+    client: TorClient<TokioNativeTlsRuntime>,
+}
+
+// When set in `arti_start_ex`'s `flags`, retry binding the SOCKS listener for
+// a short while on `AddrInUse` instead of failing immediately. This does NOT
+// set `SO_REUSEADDR` or any other socket option: `arti::socks::run_socks_proxy`
+// binds its listener internally and doesn't expose a hook for socket options,
+// so there is nothing in this crate's reach to set. What this flag actually
+// works around is `arti_proxy_stop` aborting the still-running proxy task:
+// the abort is asynchronous, so the listening socket may not be closed yet
+// by the time a caller immediately restarts on the same port. Retrying for a
+// bit gives the aborted task's listener time to actually drop.
+//
+// This relies on `run_socks_proxy` returning bind failures as a normal `Err`
+// containing an `io::Error` with kind `AddrInUse`, rather than panicking. If
+// that ever changes, `is_addr_in_use` stops matching and this flag becomes a
+// no-op rather than a hard failure.
+pub const ARTI_SOCKS_RETRY_BIND: u32 = 1 << 0;
+
+const RETRY_BIND_ATTEMPTS: u32 = 10;
+const RETRY_BIND_DELAY: std::time::Duration = std::time::Duration::from_millis(200);
+
 fn start_proxy(
     port: u16,
     client: TorClient<TokioNativeTlsRuntime>,
     progress_sender: &mpsc::Sender<String>, // Borrow sender to avoid unnecessary cloning.
-) -> JoinHandle<anyhow::Result<()>> {
+) -> ProxyHandle {
+    start_proxy_ex(tor_config::Listen::new_localhost(port), client, progress_sender, 0)
+}
+
+fn start_proxy_ex(
+    listen: tor_config::Listen,
+    client: TorClient<TokioNativeTlsRuntime>,
+    progress_sender: &mpsc::Sender<String>, // Borrow sender to avoid unnecessary cloning.
+    flags: u32,
+) -> ProxyHandle {
     println!("Starting proxy!");
     let rt = RUNTIME.as_ref().unwrap(); // Assume runtime is initialized successfully.
     let progress_sender = progress_sender.clone(); // Clone inside async block to avoid multiple mutable borrows.
-    rt.spawn(async move {
+    let proxy_client = client.clone();
+    let retry_bind = flags & ARTI_SOCKS_RETRY_BIND != 0;
+    let join = rt.spawn(async move {
         progress_sender.send("Proxy started".to_string()).await.unwrap(); // Notify that proxy has started.
-        socks::run_socks_proxy(
-            client.runtime().clone(),
-            client.clone(),
-            tor_config::Listen::new_localhost(port),
-        )
-        .await
-    })
+        let mut retries_left = if retry_bind { RETRY_BIND_ATTEMPTS } else { 0 };
+        loop {
+            let result = socks::run_socks_proxy(
+                proxy_client.runtime().clone(),
+                proxy_client.clone(),
+                listen.clone(),
+            )
+            .await;
+            match result {
+                Err(e) if retries_left > 0 && is_addr_in_use(&e) => {
+                    retries_left -= 1;
+                    tokio::time::sleep(RETRY_BIND_DELAY).await;
+                }
+                result => break result,
+            }
+        }
+    });
+    ProxyHandle { join, client }
+}
+
+// Walks an anyhow error's cause chain looking for `io::ErrorKind::AddrInUse`.
+fn is_addr_in_use(err: &anyhow::Error) -> bool {
+    err.chain()
+        .filter_map(|cause| cause.downcast_ref::<io::Error>())
+        .any(|io_err| io_err.kind() == io::ErrorKind::AddrInUse)
+}
+
+// Parses a comma-separated list of listen addresses (e.g.
+// "127.0.0.1:9050,[::1]:9050") into a `tor_config::Listen`.
+fn parse_listen(addrs: &str) -> io::Result<tor_config::Listen> {
+    addrs
+        .parse()
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, format!("{}", e)))
+}
+
+// Parses a `*const *const c_char` / `len` pair into Rust string slices
+// borrowed from the C strings.
+unsafe fn c_str_array<'a>(ptr: *const *const c_char, len: usize) -> io::Result<Vec<&'a str>> {
+    if ptr.is_null() || len == 0 {
+        return Ok(Vec::new());
+    }
+    slice::from_raw_parts(ptr, len)
+        .iter()
+        .map(|s| {
+            CStr::from_ptr(*s)
+                .to_str()
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        })
+        .collect()
+}
+
+// Populates `cfg_builder`'s bridge and pluggable-transport sections from the
+// FFI-provided bridge lines and transport descriptors. Returns an error if a
+// bridge line fails to parse or a configured PT binary does not exist on disk.
+unsafe fn configure_bridges(
+    cfg_builder: &mut arti_client::config::TorClientConfigBuilder,
+    bridge_lines: *const *const c_char,
+    bridge_lines_len: usize,
+    transports: *const PluggableTransportDescriptor,
+    transports_len: usize,
+) -> io::Result<()> {
+    let bridges_cfg = cfg_builder.bridges();
+
+    for line in c_str_array(bridge_lines, bridge_lines_len)? {
+        let bridge: BridgeConfigBuilder = line
+            .parse()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{}", e)))?;
+        bridges_cfg.bridges().push(bridge);
+    }
+
+    if !transports.is_null() {
+        for descriptor in slice::from_raw_parts(transports, transports_len) {
+            let name = CStr::from_ptr(descriptor.name)
+                .to_str()
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            let binary_path = CStr::from_ptr(descriptor.binary_path)
+                .to_str()
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            if !Path::new(binary_path).is_file() {
+                return Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("pluggable transport binary not found: {}", binary_path),
+                ));
+            }
+            let args = c_str_array(descriptor.args, descriptor.args_len)?;
+
+            let mut transport = TransportConfigBuilder::default();
+            transport.protocols(vec![name
+                .parse()
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{}", e)))?]);
+            transport.path(CfgPath::new(binary_path.to_string()));
+            // Leave `run_on_startup` at its default (false) so the channel
+            // manager spawns this PT binary on demand, the first time a
+            // bridge actually needs it, rather than at client startup.
+            transport.arguments(args.into_iter().map(str::to_string).collect::<Vec<_>>());
+            bridges_cfg.transports().push(transport);
+        }
+    }
+
+    Ok(())
 }
 
 #[no_mangle]
@@ -79,6 +260,10 @@ pub unsafe extern "C" fn arti_start(
         .state_dir(CfgPath::new(state_dir.to_string()))
         .cache_dir(CfgPath::new(cache_dir.to_string()));
     cfg_builder.address_filter().allow_onion_addrs(true);
+    // Keep the on-disk keystore on (the default), so identity keys created by
+    // `arti_onion_service_launch` persist under `state_dir` across restarts
+    // instead of being generated fresh (or rejected) each time.
+    cfg_builder.storage().keystore().enabled(true);
 
     // Build configuration or return an error.
     let cfg = unwrap_or_return!(cfg_builder.build(), err_ret);
@@ -108,6 +293,185 @@ pub unsafe extern "C" fn arti_start(
     }
 }
 
+// Like `arti_start`, but bootstraps through the given bridges instead of
+// connecting to guards directly. `bridge_lines` is a C array of
+// `bridge_lines_len` bridge line strings (the same format as a torrc
+// `Bridge` line); `transports` describes `transports_len` pluggable
+// transports (e.g. obfs4, snowflake) that those bridge lines may reference.
+// Users in censored regions should prefer this entry point over `arti_start`.
+#[no_mangle]
+pub unsafe extern "C" fn arti_start_with_bridges(
+    socks_port: u16,
+    state_dir: *const c_char,
+    cache_dir: *const c_char,
+    bridge_lines: *const *const c_char,
+    bridge_lines_len: usize,
+    transports: *const PluggableTransportDescriptor,
+    transports_len: usize,
+) -> Tor {
+    let (progress_sender, progress_receiver) = mpsc::channel::<String>(100); // Create channel for progress updates.
+
+    // Convert channels to raw pointers for FFI.
+    let progress_sender_ptr = Box::into_raw(Box::new(progress_sender)) as *mut c_void;
+    let progress_receiver_ptr = Box::into_raw(Box::new(progress_receiver)) as *mut c_void;
+
+    // Error return value with initialized raw pointers.
+    let err_ret = Tor {
+        client: ptr::null_mut(),
+        proxy: ptr::null_mut(),
+        progress_sender: progress_sender_ptr,
+        progress_receiver: progress_receiver_ptr,
+    };
+
+    // Convert C strings to Rust strings and handle errors.
+    let state_dir = unwrap_or_return!(CStr::from_ptr(state_dir).to_str(), err_ret);
+    let cache_dir = unwrap_or_return!(CStr::from_ptr(cache_dir).to_str(), err_ret);
+
+    // Create a Tokio runtime to handle asynchronous tasks.
+    let runtime = unwrap_or_return!(TokioNativeTlsRuntime::create(), err_ret);
+
+    // Configure the Tor client.
+    let mut cfg_builder = TorClientConfig::builder();
+    cfg_builder
+        .storage()
+        .state_dir(CfgPath::new(state_dir.to_string()))
+        .cache_dir(CfgPath::new(cache_dir.to_string()));
+    cfg_builder.address_filter().allow_onion_addrs(true);
+    // Keep the on-disk keystore on (the default), so identity keys created by
+    // `arti_onion_service_launch` persist under `state_dir` across restarts
+    // instead of being generated fresh (or rejected) each time.
+    cfg_builder.storage().keystore().enabled(true);
+
+    // Wire up the requested bridges and pluggable transports, or bail out
+    // with a clear error if a bridge line doesn't parse or a PT binary is
+    // missing.
+    unwrap_or_return!(
+        configure_bridges(
+            &mut cfg_builder,
+            bridge_lines,
+            bridge_lines_len,
+            transports,
+            transports_len,
+        ),
+        err_ret
+    );
+
+    // Build configuration or return an error.
+    let cfg = unwrap_or_return!(cfg_builder.build(), err_ret);
+
+    // Create and bootstrap the Tor client. The client's channel manager spawns
+    // the configured PT binaries on demand and routes guard connections
+    // through the managed SOCKS endpoint each one advertises.
+    let client = unwrap_or_return!(
+        runtime.block_on(async {
+            TorClient::with_runtime(runtime.clone())
+                .config(cfg)
+                .create_bootstrapped()
+                .await
+        }),
+        err_ret
+    );
+
+    // Convert the raw sender pointer back to its original type.
+    let progress_sender_ref = &*(progress_sender_ptr as *mut mpsc::Sender<String>);
+    let proxy_handle_box = Box::new(start_proxy(socks_port, client.clone(), progress_sender_ref));
+    let client_box = Box::new(client.clone());
+
+    // Return initialized Tor struct with raw pointers.
+    Tor {
+        client: Box::into_raw(client_box) as *mut c_void,
+        proxy: Box::into_raw(proxy_handle_box) as *mut c_void,
+        progress_sender: progress_sender_ptr,
+        progress_receiver: progress_receiver_ptr,
+    }
+}
+
+// Like `arti_start`, but lets the caller choose the SOCKS listen address(es)
+// instead of always binding `127.0.0.1:<socks_port>`. `listen_addrs` is a
+// comma-separated list of "host:port" addresses (e.g. "0.0.0.0:9050" to
+// expose the proxy on a non-loopback interface, or several addresses to
+// listen on all of them). Parse failures are reported through
+// `arti_last_error_message`.
+//
+// `flags` is a bitmask; the only bit defined today is `ARTI_SOCKS_RETRY_BIND`.
+// NOTE: that flag does not set `SO_REUSEADDR` or any other socket option —
+// `arti::socks::run_socks_proxy` gives this crate no hook to do so. It only
+// retries the bind for up to ~2 seconds, which covers rebinding right after
+// `arti_proxy_stop`. A bind failure that outlasts that window, or any other
+// socket-option need, is not handled, and is not reported back to the
+// caller: proxy startup runs detached, so a bind error that survives the
+// retries is currently dropped rather than surfaced through
+// `arti_last_error_message`.
+#[no_mangle]
+pub unsafe extern "C" fn arti_start_ex(
+    state_dir: *const c_char,
+    cache_dir: *const c_char,
+    listen_addrs: *const c_char,
+    flags: u32,
+) -> Tor {
+    let (progress_sender, progress_receiver) = mpsc::channel::<String>(100); // Create channel for progress updates.
+
+    // Convert channels to raw pointers for FFI.
+    let progress_sender_ptr = Box::into_raw(Box::new(progress_sender)) as *mut c_void;
+    let progress_receiver_ptr = Box::into_raw(Box::new(progress_receiver)) as *mut c_void;
+
+    // Error return value with initialized raw pointers.
+    let err_ret = Tor {
+        client: ptr::null_mut(),
+        proxy: ptr::null_mut(),
+        progress_sender: progress_sender_ptr,
+        progress_receiver: progress_receiver_ptr,
+    };
+
+    // Convert C strings to Rust strings and handle errors.
+    let state_dir = unwrap_or_return!(CStr::from_ptr(state_dir).to_str(), err_ret);
+    let cache_dir = unwrap_or_return!(CStr::from_ptr(cache_dir).to_str(), err_ret);
+    let listen_addrs = unwrap_or_return!(CStr::from_ptr(listen_addrs).to_str(), err_ret);
+    let listen = unwrap_or_return!(parse_listen(listen_addrs), err_ret);
+
+    // Create a Tokio runtime to handle asynchronous tasks.
+    let runtime = unwrap_or_return!(TokioNativeTlsRuntime::create(), err_ret);
+
+    // Configure the Tor client.
+    let mut cfg_builder = TorClientConfig::builder();
+    cfg_builder
+        .storage()
+        .state_dir(CfgPath::new(state_dir.to_string()))
+        .cache_dir(CfgPath::new(cache_dir.to_string()));
+    cfg_builder.address_filter().allow_onion_addrs(true);
+    // Keep the on-disk keystore on (the default), so identity keys created by
+    // `arti_onion_service_launch` persist under `state_dir` across restarts
+    // instead of being generated fresh (or rejected) each time.
+    cfg_builder.storage().keystore().enabled(true);
+
+    // Build configuration or return an error.
+    let cfg = unwrap_or_return!(cfg_builder.build(), err_ret);
+
+    // Create and bootstrap the Tor client.
+    let client = unwrap_or_return!(
+        runtime.block_on(async {
+            TorClient::with_runtime(runtime.clone())
+                .config(cfg)
+                .create_bootstrapped()
+                .await
+        }),
+        err_ret
+    );
+
+    // Convert the raw sender pointer back to its original type.
+    let progress_sender_ref = &*(progress_sender_ptr as *mut mpsc::Sender<String>);
+    let proxy_handle_box = Box::new(start_proxy_ex(listen, client.clone(), progress_sender_ref, flags));
+    let client_box = Box::new(client.clone());
+
+    // Return initialized Tor struct with raw pointers.
+    Tor {
+        client: Box::into_raw(client_box) as *mut c_void,
+        proxy: Box::into_raw(proxy_handle_box) as *mut c_void,
+        progress_sender: progress_sender_ptr,
+        progress_receiver: progress_receiver_ptr,
+    }
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn arti_client_bootstrap(client: *mut c_void) -> bool {
     // Convert raw pointer back to TorClient.
@@ -137,14 +501,201 @@ pub unsafe extern "C" fn arti_client_set_dormant(client: *mut c_void, soft_mode:
     Box::leak(client); // Prevents the client from being deallocated.
 }
 
+// Releases the client handle returned by `arti_start`/`arti_start_with_bridges`
+// or `arti_isolated_client`. Once the last clone of the client is dropped,
+// arti also stops any pluggable-transport subprocesses it was managing for it.
+#[no_mangle]
+pub unsafe extern "C" fn arti_client_free(client: *mut c_void) {
+    if client.is_null() {
+        return;
+    }
+    drop(Box::from_raw(client as *mut TorClient<TokioNativeTlsRuntime>));
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn arti_proxy_stop(proxy: *mut c_void) {
-    // Convert raw pointer back to the join handle.
+    // Convert raw pointer back to the proxy handle.
     let proxy = unsafe {
-        Box::from_raw(proxy as *mut JoinHandle<anyhow::Result<()>>)
+        Box::from_raw(proxy as *mut ProxyHandle)
     };
 
-    proxy.abort(); // Stop the proxy.
+    proxy.join.abort(); // Stop the proxy.
+    // Dropping `proxy` here only drops the client clone the proxy task held.
+    // `Tor.client` (and any `arti_isolated_client` handle derived from it)
+    // holds its own clone, so this alone does NOT tear down any
+    // pluggable-transport subprocesses (e.g. obfs4proxy) the client is
+    // managing. Callers must also call `arti_client_free` on every remaining
+    // client/isolated-client handle for the last clone to drop and arti to
+    // kill those subprocesses.
+}
+
+// Bundles a `DataStream` with the runtime that drives its reactor, so
+// `arti_stream_read`/`write` block on the same runtime the stream's client
+// was built with rather than the unrelated global `RUNTIME`.
+struct StreamHandle {
+    stream: DataStream,
+    runtime: TokioNativeTlsRuntime,
+}
+
+// Opens a Tor circuit to `host:port` (an ordinary hostname/IP or a `.onion`
+// address) and returns an opaque stream handle, without going through a local
+// SOCKS proxy. Useful on platforms where running a process-wide SOCKS proxy
+// is awkward, e.g. mobile or embedded. Returns NULL on failure; check
+// `arti_last_error_message`.
+#[no_mangle]
+pub unsafe extern "C" fn arti_connect(
+    client: *mut c_void,
+    host: *const c_char,
+    port: u16,
+) -> *mut c_void {
+    let client = &*(client as *mut TorClient<TokioNativeTlsRuntime>);
+    let host = unwrap_or_return!(CStr::from_ptr(host).to_str(), ptr::null_mut());
+
+    let stream = unwrap_or_return!(
+        client.runtime().block_on(client.connect((host, port))),
+        ptr::null_mut()
+    );
+    Box::into_raw(Box::new(StreamHandle {
+        stream,
+        runtime: client.runtime().clone(),
+    })) as *mut c_void
+}
+
+// Returns a new client handle sharing `client`'s bootstrapped state but with
+// a fresh isolation group, so circuits built through it never share with
+// circuits built through `client` or any other isolated client derived from
+// it. Mirrors `TorClient::isolated_client`. Free the result with
+// `arti_client_free` once it is no longer needed.
+#[no_mangle]
+pub unsafe extern "C" fn arti_isolated_client(client: *mut c_void) -> *mut c_void {
+    let client = &*(client as *mut TorClient<TokioNativeTlsRuntime>);
+    Box::into_raw(Box::new(client.isolated_client())) as *mut c_void
+}
+
+// Like `arti_connect`, but attaches `isolation_token` to the stream. Two
+// calls with different tokens are guaranteed to never share a circuit, even
+// through the same client; two calls with the same token may share one. This
+// lets embedders keep, say, two user accounts in the same app unlinkable.
+//
+// `isolation_token` is looked up in a process-wide table the first time it
+// is seen and kept there so repeated calls with the same id keep mapping to
+// the same isolation group; call `arti_release_isolation_token` once a
+// logical session ends so that table doesn't grow unboundedly.
+#[no_mangle]
+pub unsafe extern "C" fn arti_connect_isolated(
+    client: *mut c_void,
+    host: *const c_char,
+    port: u16,
+    isolation_token: u64,
+) -> *mut c_void {
+    let client = &*(client as *mut TorClient<TokioNativeTlsRuntime>);
+    let host = unwrap_or_return!(CStr::from_ptr(host).to_str(), ptr::null_mut());
+
+    let mut prefs = StreamPrefs::new();
+    prefs.set_isolation(isolation_token_for(isolation_token));
+
+    let stream = unwrap_or_return!(
+        client
+            .runtime()
+            .block_on(client.connect_with_prefs((host, port), &prefs)),
+        ptr::null_mut()
+    );
+    Box::into_raw(Box::new(StreamHandle {
+        stream,
+        runtime: client.runtime().clone(),
+    })) as *mut c_void
+}
+
+// Reads up to `buf_len` bytes from the stream into `buf`. Returns the number
+// of bytes read, 0 on EOF, or -1 on error (check `arti_last_error_message`).
+#[no_mangle]
+pub unsafe extern "C" fn arti_stream_read(stream: *mut c_void, buf: *mut u8, buf_len: usize) -> isize {
+    let handle = &mut *(stream as *mut StreamHandle);
+    let out = slice::from_raw_parts_mut(buf, buf_len);
+    unwrap_or_return!(handle.runtime.block_on(handle.stream.read(out)), -1) as isize
+}
+
+// Writes up to `data_len` bytes from `data` to the stream. Returns the number
+// of bytes written, or -1 on error (check `arti_last_error_message`).
+#[no_mangle]
+pub unsafe extern "C" fn arti_stream_write(
+    stream: *mut c_void,
+    data: *const u8,
+    data_len: usize,
+) -> isize {
+    let handle = &mut *(stream as *mut StreamHandle);
+    let input = slice::from_raw_parts(data, data_len);
+    unwrap_or_return!(handle.runtime.block_on(handle.stream.write(input)), -1) as isize
+}
+
+// Closes the stream and releases the circuit it held open.
+#[no_mangle]
+pub unsafe extern "C" fn arti_stream_close(stream: *mut c_void) {
+    if stream.is_null() {
+        return;
+    }
+    drop(Box::from_raw(stream as *mut StreamHandle));
+}
+
+// Callback invoked with bootstrap progress; `percent` is the ready fraction
+// scaled 0-100 and `summary` is a short, human-readable status message valid
+// only for the duration of the call.
+type BootstrapCallback = extern "C" fn(ctx: *mut c_void, percent: u8, summary: *const c_char);
+
+// Wraps a caller-supplied context pointer so it can be moved into the
+// runtime task below; the caller is responsible for ensuring it is safe to
+// access from another thread.
+struct SendPtr(*mut c_void);
+unsafe impl Send for SendPtr {}
+
+// Subscribes to `client`'s bootstrap status stream and invokes `callback` on
+// every change for as long as the subscription is alive. This is not limited
+// to initial bootstrap: the client keeps emitting status changes later too
+// (e.g. going dormant, or re-bootstrapping), and `callback` keeps firing for
+// each one until `arti_clear_bootstrap_callback` is called on the returned
+// handle or `client` itself is freed.
+//
+// `callback` runs on a Tokio runtime worker thread, so it must be cheap and
+// thread-safe; do not block or perform long-running work in it.
+//
+// `ctx` must stay valid for the entire lifetime of the subscription, i.e.
+// until `arti_clear_bootstrap_callback` has returned for the handle this
+// function returns — not merely until bootstrap first reaches 100%. Call
+// `arti_clear_bootstrap_callback` before freeing whatever `ctx` points to.
+#[no_mangle]
+pub unsafe extern "C" fn arti_set_bootstrap_callback(
+    client: *mut c_void,
+    callback: BootstrapCallback,
+    ctx: *mut c_void,
+) -> *mut c_void {
+    let client = &*(client as *mut TorClient<TokioNativeTlsRuntime>);
+    let mut events = client.bootstrap_events();
+    let rt = RUNTIME.as_ref().unwrap();
+    let ctx = SendPtr(ctx);
+    let join = rt.spawn(async move {
+        let ctx = ctx; // Moved in whole so the raw pointer crosses the await points.
+        while let Some(status) = events.next().await {
+            let percent = (status.as_frac() * 100.0).round() as u8;
+            let summary = CString::new(status.to_string()).unwrap_or_default();
+            callback(ctx.0, percent, summary.as_ptr());
+        }
+    });
+    Box::into_raw(Box::new(join)) as *mut c_void
+}
+
+// Unregisters a bootstrap callback previously registered with
+// `arti_set_bootstrap_callback`, stopping further invocations. Safe to call
+// once the caller is ready to free `ctx`; must be called before doing so.
+#[no_mangle]
+pub unsafe extern "C" fn arti_clear_bootstrap_callback(handle: *mut c_void) {
+    if handle.is_null() {
+        return;
+    }
+    let join = Box::from_raw(handle as *mut JoinHandle<()>);
+    join.abort();
+    // Wait for the task to actually stop, so a callback that was already
+    // running has finished with `ctx` by the time this returns.
+    let _ = RUNTIME.as_ref().unwrap().block_on(*join);
 }
 
 #[no_mangle]
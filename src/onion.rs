@@ -0,0 +1,160 @@
+// FFI surface for hosting onion services. Companion to the outbound-only
+// `allow_onion_addrs` support in `lib.rs`: this lets an embedder publish a
+// hidden service instead of just connecting to one.
+
+use crate::RUNTIME;
+use arti_client::TorClient;
+use futures::StreamExt;
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::sync::Arc;
+use std::{ptr, slice};
+use tokio::io::copy_bidirectional;
+use tokio::net::TcpStream;
+use tokio::task::JoinHandle;
+use tor_cell::relaycell::msg::{Connected, End, EndReason};
+use tor_hsservice::config::OnionServiceConfigBuilder;
+use tor_hsservice::handle_rend_requests;
+use tor_hsservice::{HsNickname, IncomingStreamRequest, RunningOnionService};
+use tor_rtcompat::tokio::TokioNativeTlsRuntime;
+
+// One virtual-port -> local-target mapping for a published onion service.
+// Inbound rendezvous streams asking for `virtual_port` are forwarded to
+// `127.0.0.1:target_port`.
+#[repr(C)]
+pub struct OnionServicePortMapping {
+    virtual_port: u16,
+    target_port: u16,
+}
+
+// Configuration for `arti_onion_service_launch`. `nickname` identifies the
+// service's key storage under the client's existing `state_dir`, so the
+// published address stays stable across restarts.
+#[repr(C)]
+pub struct OnionServiceConfig {
+    nickname: *const c_char,
+    ports: *const OnionServicePortMapping,
+    ports_len: usize,
+}
+
+// A launched onion service and the background task forwarding its inbound
+// streams to their configured local targets.
+pub struct OnionServiceHandle {
+    service: Arc<RunningOnionService>,
+    forward_task: JoinHandle<()>,
+}
+
+// Launches an onion service as described by `config` on `client`, and returns
+// a handle to it, or NULL on failure (check `arti_last_error_message`). Free
+// the result with `arti_onion_service_stop`.
+//
+// The service's identity key is persisted under the client's `state_dir` and
+// survives restarts only because `arti_start`/`arti_start_with_bridges`/
+// `arti_start_ex` keep the on-disk keystore enabled in the client config; a
+// client built with the keystore disabled would get a fresh (or rejected)
+// identity on every launch.
+#[no_mangle]
+pub unsafe extern "C" fn arti_onion_service_launch(
+    client: *mut c_void,
+    config: *const OnionServiceConfig,
+) -> *mut c_void {
+    let client = &*(client as *mut TorClient<TokioNativeTlsRuntime>);
+    let config = &*config;
+
+    let nickname = unwrap_or_return!(CStr::from_ptr(config.nickname).to_str(), ptr::null_mut());
+    let nickname: HsNickname = unwrap_or_return!(
+        nickname
+            .parse()
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("{}", e))),
+        ptr::null_mut()
+    );
+
+    let port_map: Vec<(u16, u16)> = if config.ports.is_null() || config.ports_len == 0 {
+        Vec::new()
+    } else {
+        slice::from_raw_parts(config.ports, config.ports_len)
+            .iter()
+            .map(|mapping| (mapping.virtual_port, mapping.target_port))
+            .collect()
+    };
+
+    let svc_config = unwrap_or_return!(
+        OnionServiceConfigBuilder::default()
+            .nickname(nickname)
+            .build()
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("{}", e))),
+        ptr::null_mut()
+    );
+
+    let (service, request_stream) = unwrap_or_return!(
+        client.launch_onion_service(svc_config),
+        ptr::null_mut()
+    );
+
+    let rt = RUNTIME.as_ref().unwrap();
+    let forward_task = rt.spawn(forward_onion_requests(request_stream, port_map));
+
+    Box::into_raw(Box::new(OnionServiceHandle {
+        service,
+        forward_task,
+    })) as *mut c_void
+}
+
+// Accepts each inbound rendezvous stream in turn and splices it to whichever
+// local target its requested virtual port maps to, closing streams for
+// unmapped ports.
+async fn forward_onion_requests(
+    request_stream: impl futures::Stream<Item = tor_hsservice::RendRequest> + Unpin,
+    port_map: Vec<(u16, u16)>,
+) {
+    let mut stream_requests = Box::pin(handle_rend_requests(request_stream));
+    while let Some(stream_request) = stream_requests.next().await {
+        // Reject the individual stream (not the whole circuit) for requests
+        // we won't serve, so other streams multiplexed on the same
+        // rendezvous circuit are left alone.
+        let requested_port = match stream_request.request() {
+            IncomingStreamRequest::Begin(begin) => begin.port(),
+            _ => {
+                let _ = stream_request.reject(End::new_with_reason(EndReason::NOTIMPLEMENTED)).await;
+                continue;
+            }
+        };
+
+        let Some(&(_, target_port)) = port_map.iter().find(|(vport, _)| *vport == requested_port)
+        else {
+            let _ = stream_request.reject(End::new_with_reason(EndReason::CONNECTREFUSED)).await;
+            continue;
+        };
+
+        tokio::spawn(async move {
+            let mut onion_stream = match stream_request.accept(Connected::new_empty()).await {
+                Ok(s) => s,
+                Err(_) => return,
+            };
+            if let Ok(mut local) = TcpStream::connect(("127.0.0.1", target_port)).await {
+                let _ = copy_bidirectional(&mut onion_stream, &mut local).await;
+            }
+        });
+    }
+}
+
+// Returns the service's `.onion` address as a C string, or NULL if it is not
+// yet known (the service is still publishing its descriptor).
+#[no_mangle]
+pub unsafe extern "C" fn arti_onion_service_address(handle: *mut c_void) -> *const c_char {
+    let handle = &*(handle as *mut OnionServiceHandle);
+    match handle.service.onion_address() {
+        Some(addr) => CString::new(addr.to_string()).unwrap().into_raw(),
+        None => ptr::null(),
+    }
+}
+
+// Stops forwarding inbound streams and tears the onion service down.
+#[no_mangle]
+pub unsafe extern "C" fn arti_onion_service_stop(handle: *mut c_void) {
+    if handle.is_null() {
+        return;
+    }
+    let handle = Box::from_raw(handle as *mut OnionServiceHandle);
+    handle.forward_task.abort();
+    drop(handle.service); // Unpublishes the descriptor and closes listeners.
+}